@@ -21,7 +21,7 @@ impl Weight for CaesarKeys {
 struct CaesarRange(std::ops::RangeInclusive<u8>);
 
 impl Step<u8, CaesarKeys> for CaesarRange {
-    fn step(&self, &input: &u8) -> Option<CaesarKeys> {
+    fn step(&self, _index: usize, &input: &u8) -> Option<CaesarKeys> {
         // If C=P+K (mod 128), then K=C-P (mod 128).
         let mask = (1u128 << self.0.len()) - 1;
         let key_start = input.wrapping_sub(*self.0.end()) % 128;
@@ -25,6 +25,10 @@ pub trait Weight: Sized {
     /// allowed to fail. It may ignore either match result, however, if the
     /// static priorities are sufficient.
     fn merge(&mut self, other: Self);
+    /// Record that the thread carrying this weight reached [Inst::Save] for
+    /// the given slot at the given input offset. Most weights don't track
+    /// capture groups and can ignore this; see [Captures] for one that does.
+    fn save(&mut self, _slot: usize, _offset: usize) {}
 }
 
 /// A trivial weight for determining whether the input matched the language.
@@ -40,26 +44,96 @@ impl Weight for () {
     fn merge(&mut self, _: Self) {}
 }
 
+/// A weight that records the offset of each [Inst::Save] slot reached while
+/// matching, for extracting capture groups the way a tagged NFA does.
+/// Slots are numbered starting from 0; a slot that was never reached stays
+/// `None`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Captures(Vec<Option<usize>>);
+
+impl Captures {
+    /// The offset recorded for the given slot, or `None` if this match
+    /// never reached an [Inst::Save] for that slot.
+    pub fn get(&self, slot: usize) -> Option<usize> {
+        self.0.get(slot).copied().flatten()
+    }
+}
+
+impl Weight for Captures {
+    fn success() -> Self {
+        Captures(Vec::new())
+    }
+
+    /// Slots filled in by either side carry over; a slot both sides filled
+    /// with different offsets means this concatenation isn't consistent.
+    fn concat(&self, other: &Self) -> Option<Self> {
+        let len = self.0.len().max(other.0.len());
+        let mut slots = Vec::with_capacity(len);
+        for i in 0..len {
+            slots.push(match (self.get(i), other.get(i)) {
+                (Some(a), Some(b)) if a != b => return None,
+                (a, b) => a.or(b),
+            });
+        }
+        Some(Captures(slots))
+    }
+
+    /// The higher-priority alternative's slots always win.
+    fn merge(&mut self, _other: Self) {}
+
+    fn save(&mut self, slot: usize, offset: usize) {
+        if slot >= self.0.len() {
+            self.0.resize(slot + 1, None);
+        }
+        self.0[slot] = Some(offset);
+    }
+}
+
+/// The set of values a [Step] might accept, expressed as a union of
+/// inclusive ranges. See [Step::accepts_set].
+pub struct RangeInclusiveSet<T>(Vec<std::ops::RangeInclusive<T>>);
+
+impl<T: PartialOrd> RangeInclusiveSet<T> {
+    /// Whether any range in this set contains `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        self.0.iter().any(|range| range.contains(value))
+    }
+}
+
 /// For types which can describe whether a single input matches.
 pub trait Step<T, W> {
-    fn step(&self, input: &T) -> Option<W>;
+    /// `index` is the offset of `input` within the overall input, for steps
+    /// that need to know where they are, such as ones that build [Captures].
+    fn step(&self, index: usize, input: &T) -> Option<W>;
+
+    /// The set of inputs this step can possibly accept, for steps where
+    /// that's practical to enumerate. Used to build a start-byte prefilter
+    /// for unanchored search (see [Pattern::search_prefiltered]); steps
+    /// that don't override this just make the prefilter unavailable.
+    fn accepts_set(&self) -> Option<RangeInclusiveSet<T>> {
+        None
+    }
 }
 
-impl<T: PartialOrd, W: Weight> Step<T, W> for std::ops::RangeInclusive<T> {
+impl<T: PartialOrd + Clone, W: Weight> Step<T, W> for std::ops::RangeInclusive<T> {
     /// Returns [Weight::success] if the input is within this range.
-    fn step(&self, input: &T) -> Option<W> {
+    fn step(&self, _index: usize, input: &T) -> Option<W> {
         if self.contains(input) {
             Some(W::success())
         } else {
             None
         }
     }
+
+    fn accepts_set(&self) -> Option<RangeInclusiveSet<T>> {
+        Some(RangeInclusiveSet(vec![self.clone()]))
+    }
 }
 
-impl<T, W, F: Fn(&T) -> Option<W>> Step<T, W> for F {
+impl<T, W, F: Fn(usize, &T) -> Option<W>> Step<T, W> for F {
     /// Returns the weight computed by the given function or closure.
-    fn step(&self, input: &T) -> Option<W> {
-        self(input)
+    fn step(&self, index: usize, input: &T) -> Option<W> {
+        self(index, input)
     }
 }
 
@@ -77,15 +151,214 @@ pub enum Inst<S, PC = u16> {
     /// Continue executing this thread at the instruction after this one, and
     /// add a lower-priority thread that starts at the given program counter.
     PreferNext(PC),
+    /// Record the current input offset into the given slot of the running
+    /// weight (see [Weight::save]), then continue at the instruction after
+    /// this one. Doesn't consume any input.
+    Save(usize),
+}
+
+/// Build a copy of `pattern` whose control flow runs back-to-front, for
+/// recovering where a match started when only its end offset is known:
+/// mirroring regex-automata's reverse-DFA technique, running the reversed pattern
+/// backward over the input starting from that end offset finds the earliest
+/// point it could have begun.
+///
+/// [Inst::Step]s carry over unchanged; [Inst::Jump], [Inst::PreferTarget],
+/// and [Inst::PreferNext] have their targets recomputed for the new
+/// positions, and the latter two swap roles with each other, since the
+/// fall-through edge they didn't take now points the other way.
+///
+/// The weight type driving a reverse pass needs a [Weight::concat] that's
+/// associative and commutative enough to give the same answer read
+/// back-to-front as forward; the recognition weight `()` always qualifies,
+/// since it carries no payload to get out of order. This transform also
+/// assumes `pattern` has no backward jumps, i.e. every [Inst::Jump],
+/// [Inst::PreferTarget], or [Inst::PreferNext] only ever targets a later
+/// instruction (loops like `(a-z)*` violate this), and that no program
+/// point is reached from more than one predecessor. The latter rules out
+/// not just loops but ordinary forward control flow that rejoins a shared
+/// continuation, such as alternation (`A|B`) or an optional branch
+/// (`A?`) followed by more pattern: mirroring one instruction at a time
+/// can't also merge the multiple predecessors that converge on it back
+/// into a single reversed point.
+pub fn reverse<S: Clone, PC: Into<usize> + Copy>(pattern: &[Inst<S, PC>]) -> Vec<Inst<S, PC>>
+where
+    usize: TryInto<PC>,
+{
+    let len = pattern.len();
+    for (pc, inst) in pattern.iter().enumerate() {
+        let target = match inst {
+            Inst::Jump(to) | Inst::PreferTarget(to) | Inst::PreferNext(to) => Some((*to).into()),
+            Inst::Step(_) | Inst::Save(_) => None,
+        };
+        if let Some(target) = target {
+            assert!(
+                target > pc,
+                "reverse() can't handle a backward jump from {pc} to {target}: \
+                 this pattern has a loop, which only a single forward-only \
+                 instruction can't also reverse"
+            );
+            assert!(
+                target <= len,
+                "reverse() got a jump from {pc} to {target}, which is past the \
+                 end of a pattern of length {len}"
+            );
+        }
+    }
+    // Reject any program point reached from more than one distinct
+    // instruction, whether by an explicit jump or by falling through from
+    // the instruction before it. A diamond like `(A|B)C`'s shared `C`, or
+    // an optional suffix's shared continuation, converges two predecessors
+    // on one point; reversing turns predecessors into successors, but the
+    // single mirrored instruction at that point only has room for the
+    // successors its own (at most two) outgoing edges describe, not the
+    // distinct predecessors that used to lead into it.
+    let mut predecessor: Vec<Option<usize>> = vec![None; len + 1];
+    let mut mark = |from: usize, to: usize| match predecessor[to] {
+        Some(other) if other != from => panic!(
+            "reverse() can't handle program point {to} being reached from both {other} \
+             and {from}: this pattern's control flow rejoins, which a single reversed \
+             instruction at {to} can't also split back apart"
+        ),
+        _ => predecessor[to] = Some(from),
+    };
+    for (pc, inst) in pattern.iter().enumerate() {
+        match inst {
+            Inst::Step(_) | Inst::Save(_) => mark(pc, pc + 1),
+            Inst::Jump(to) => mark(pc, (*to).into()),
+            Inst::PreferTarget(to) => {
+                mark(pc, (*to).into());
+                mark(pc, pc + 1);
+            }
+            Inst::PreferNext(to) => {
+                mark(pc, pc + 1);
+                mark(pc, (*to).into());
+            }
+        }
+    }
+    // A target of `len` means "walk off the end of the pattern", i.e. accept
+    // immediately; that's just as legitimate a target backward as forward, so
+    // it mirrors to itself rather than going through the `len - 1 - to`
+    // reflection used for real instruction indices (which would underflow).
+    let mirror = |to: PC| -> PC {
+        let to = to.into();
+        let to = if to == len { len } else { len - 1 - to };
+        to.try_into()
+            .unwrap_or_else(|_| panic!("PC {} out of range", to))
+    };
+    pattern
+        .iter()
+        .rev()
+        .map(|inst| match inst {
+            Inst::Step(s) => Inst::Step(s.clone()),
+            Inst::Save(slot) => Inst::Save(*slot),
+            Inst::Jump(to) => Inst::Jump(mirror(*to)),
+            Inst::PreferTarget(to) => Inst::PreferNext(mirror(*to)),
+            Inst::PreferNext(to) => Inst::PreferTarget(mirror(*to)),
+        })
+        .collect()
+}
+
+/// The priority-ordered list of [Step] instructions reachable from some
+/// program counter without consuming any input, found by precomputing the
+/// epsilon-closure over [Inst::Jump], [Inst::PreferTarget],
+/// [Inst::PreferNext], and [Inst::Save]. Each entry names the program
+/// counter of the `Step` itself, the slots any [Inst::Save]s along the way
+/// there should record, and the program counter a thread should continue at
+/// after that `Step` succeeds.
+struct Closure {
+    steps: Vec<(usize, Vec<usize>, usize)>,
+    /// Every way this program counter can walk off the end of the pattern
+    /// without consuming any input, i.e. accept the empty continuation,
+    /// recorded as the slots any [Inst::Save]s along that path should
+    /// record.
+    accepting: Vec<Vec<usize>>,
+}
+
+fn close<S, PC: Into<usize> + Copy>(
+    pattern: &[Inst<S, PC>],
+    pc: usize,
+    saves: &mut Vec<usize>,
+    steps: &mut Vec<(usize, Vec<usize>, usize)>,
+    accepting: &mut Vec<Vec<usize>>,
+) {
+    match pattern.get(pc) {
+        // Walking off the end of the pattern indicates a successful match.
+        None => accepting.push(saves.clone()),
+
+        Some(Inst::Step(_)) => steps.push((pc, saves.clone(), pc + 1)),
+        Some(&Inst::Jump(to)) => close(pattern, to.into(), saves, steps, accepting),
+        Some(&Inst::Save(slot)) => {
+            saves.push(slot);
+            close(pattern, pc + 1, saves, steps, accepting);
+            saves.pop();
+        }
+        Some(&Inst::PreferTarget(to)) => {
+            close(pattern, to.into(), saves, steps, accepting);
+            close(pattern, pc + 1, saves, steps, accepting);
+        }
+        Some(&Inst::PreferNext(to)) => {
+            close(pattern, pc + 1, saves, steps, accepting);
+            close(pattern, to.into(), saves, steps, accepting);
+        }
+    }
 }
 
 pub struct Pattern<'a, S, PC, T, W> {
     pattern: &'a [Inst<S, PC>],
+    closures: Vec<Closure>,
     threads: Vec<(PC, W)>,
     index: HashMap<PC, usize>,
+    result: Option<W>,
+    offset: usize,
     _phantom: PhantomData<T>,
 }
 
+/// The threads queued to run at the next input position during
+/// [Pattern::search] and [Pattern::search_prefiltered], along with a dedup
+/// index from program counter to that thread's position in `threads`, so
+/// that two threads reaching the same program counter get merged instead of
+/// both surviving.
+///
+/// Dedup keys only on program counter, not on the start offset each thread
+/// carries: two threads that began at different positions but converge on
+/// the same `pc` get collapsed by [Weight::merge] the same way two threads
+/// that began together would, and the loser's start offset is discarded.
+/// That's exactly right for [Weight::merge] implementations like
+/// [Captures]'s that just keep the higher-priority side's data, but a
+/// `merge` that meaningfully blends both sides' weights would end up
+/// attributing data from the discarded, unrelated start to the span this
+/// module reports for the surviving thread. See [Pattern::search]'s
+/// documentation for the restriction this implies.
+struct Frontier<PC, W> {
+    threads: Vec<(PC, usize, W)>,
+    index: HashMap<PC, usize>,
+}
+
+impl<PC, W> Default for Frontier<PC, W> {
+    fn default() -> Self {
+        Frontier {
+            threads: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<PC: Copy + Eq + std::hash::Hash, W: Weight> Frontier<PC, W> {
+    fn push(&mut self, pc: PC, start: usize, weight: W) {
+        match self.index.entry(pc) {
+            Entry::Vacant(entry) => {
+                entry.insert(self.threads.len());
+                self.threads.push((pc, start, weight));
+            }
+            Entry::Occupied(entry) => {
+                let (_pc, _start, old) = &mut self.threads[*entry.get()];
+                old.merge(weight);
+            }
+        }
+    }
+}
+
 impl<'a, S, PC, T, W> Pattern<'a, S, PC, T, W>
 where
     S: Step<T, W>,
@@ -94,64 +367,267 @@ where
     usize: TryInto<PC>,
 {
     pub fn new(pattern: &'a [Inst<S, PC>]) -> Self {
+        let closures = (0..=pattern.len())
+            .map(|pc| {
+                let mut steps = Vec::new();
+                let mut accepting = Vec::new();
+                close(pattern, pc, &mut Vec::new(), &mut steps, &mut accepting);
+                Closure { steps, accepting }
+            })
+            .collect();
         Pattern {
             pattern,
+            closures,
             threads: Vec::new(),
             index: HashMap::new(),
+            result: None,
+            offset: 0,
             _phantom: PhantomData,
         }
     }
 
     pub fn eval<B: Borrow<T>, I: IntoIterator<Item = B>>(&mut self, input: I) -> Option<W> {
+        self.start();
+        self.feed(input);
+        self.finish()
+    }
+
+    /// Reset this pattern to start matching a new, possibly chunked, input.
+    /// Call this once before the first [Pattern::feed], then call `feed` as
+    /// many times as needed as more input arrives, and finally call
+    /// [Pattern::finish] to get the accumulated result.
+    pub fn start(&mut self) {
         self.threads.clear();
         self.threads.push((Self::as_pc(0), W::success()));
-        let mut result = None;
-        for input in input {
+        self.result = None;
+        self.offset = 0;
+    }
+
+    /// Continue matching against the next chunk of input, picking up the
+    /// live threads and accumulated result left by the previous call to
+    /// [Pattern::feed] (or [Pattern::start]). Returns the best match found
+    /// so far, which may be revised by later chunks. Use [Pattern::is_alive]
+    /// to tell whether it's worth feeding more input.
+    pub fn feed<B: Borrow<T>, I: IntoIterator<Item = B>>(&mut self, chunk: I) -> Option<W> {
+        for input in chunk {
             let input = input.borrow();
             let mut matched = None;
             self.index.clear();
             for (pc, weight) in std::mem::take(&mut self.threads) {
-                matched = merge(matched, self.add(pc.into(), &weight, input));
+                matched = merge(matched, self.add(pc.into(), self.offset, &weight, input));
             }
-            result = matched.or(result);
+            self.offset += 1;
+            self.result = matched.or(self.result.take());
             if self.threads.is_empty() {
                 break;
             }
         }
-        result
+        // A thread whose closure is accepting doesn't need another input
+        // element to prove it: it's already a complete match ending here.
+        self.result = self.accepting().or(self.result.take());
+        self.result.clone()
+    }
+
+    /// Finish a chunked match started with [Pattern::start], returning the
+    /// best match found across all the chunks fed to [Pattern::feed].
+    pub fn finish(&mut self) -> Option<W> {
+        self.result.take()
+    }
+
+    /// Whether any thread is still alive, i.e. whether feeding more input
+    /// could still change the result of this match.
+    pub fn is_alive(&self) -> bool {
+        !self.threads.is_empty()
     }
 
-    fn add(&mut self, pc: usize, weight: &W, input: &T) -> Option<W> {
-        match self.pattern.get(pc) {
-            // Walking off the end of the pattern indicates a successful match.
-            None => Some(weight.clone()),
-
-            Some(Inst::Step(s)) => {
-                if let Some(new) = s.step(input).and_then(|cur| weight.concat(&cur)) {
-                    let next = Self::as_pc(pc + 1);
-                    match self.index.entry(next) {
-                        Entry::Vacant(entry) => {
-                            entry.insert(self.threads.len());
-                            self.threads.push((next, new));
-                        }
-                        Entry::Occupied(entry) => {
-                            let (_pc, old) = &mut self.threads[*entry.get()];
-                            old.merge(new);
-                        }
+    /// Check whether any currently live thread's program counter can walk
+    /// off the end of the pattern without consuming more input, i.e. whether
+    /// the match could end right here. Unlike [Pattern::add], this doesn't
+    /// spawn any new threads, since there's no input left to run them on.
+    fn accepting(&self) -> Option<W> {
+        let mut matched = None;
+        for (pc, weight) in &self.threads {
+            for saves in &self.closures[(*pc).into()].accepting {
+                let mut new = weight.clone();
+                for &slot in saves {
+                    new.save(slot, self.offset);
+                }
+                matched = merge(matched, Some(new));
+            }
+        }
+        matched
+    }
+
+    /// Run every [Step] reachable from `pc` without consuming input (as
+    /// precomputed in `self.closures`), enqueueing or merging the resulting
+    /// threads. Returns the weight of a successful match ending here, if the
+    /// closure of `pc` can walk off the end of the pattern.
+    fn add(&mut self, pc: usize, offset: usize, weight: &W, input: &T) -> Option<W> {
+        let mut matched = None;
+        for saves in &self.closures[pc].accepting {
+            let mut new = weight.clone();
+            for &slot in saves {
+                new.save(slot, offset);
+            }
+            matched = merge(matched, Some(new));
+        }
+        for i in 0..self.closures[pc].steps.len() {
+            let (step, saves, next) = &self.closures[pc].steps[i];
+            let (step, next) = (*step, *next);
+            let Some(Inst::Step(s)) = self.pattern.get(step) else {
+                unreachable!("closures only record the program counters of Step instructions")
+            };
+            let mut weight = weight.clone();
+            for &slot in saves {
+                weight.save(slot, offset);
+            }
+            if let Some(new) = s.step(offset, input).and_then(|cur| weight.concat(&cur)) {
+                let next = Self::as_pc(next);
+                match self.index.entry(next) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(self.threads.len());
+                        self.threads.push((next, new));
+                    }
+                    Entry::Occupied(entry) => {
+                        let (_pc, old) = &mut self.threads[*entry.get()];
+                        old.merge(new);
                     }
                 }
-                None
             }
-            Some(&Inst::Jump(to)) => self.add(to.into(), weight, input),
-            Some(&Inst::PreferTarget(to)) => merge(
-                self.add(to.into(), weight, input),
-                self.add(pc + 1, weight, input),
-            ),
-            Some(&Inst::PreferNext(to)) => merge(
-                self.add(pc + 1, weight, input),
-                self.add(to.into(), weight, input),
-            ),
         }
+        matched
+    }
+
+    /// Find the leftmost match anywhere in `input`, rather than requiring a
+    /// match to start at the first element like [Pattern::eval] does.
+    ///
+    /// At every input position, a fresh thread may start at program counter
+    /// 0, but it's given the lowest priority so that a thread which started
+    /// earlier always wins ties, which is what makes the match leftmost.
+    /// Returns the start and end offsets of the match, along with its
+    /// weight.
+    ///
+    /// Threads that start at different offsets but converge on the same
+    /// program counter are deduped by [Weight::merge] just like threads that
+    /// started together, and the discarded thread's start offset is lost in
+    /// the process (see [Frontier]). That's safe for a `merge` that just
+    /// keeps its higher-priority side, as [Captures] does, but a `merge`
+    /// that meaningfully combines both sides' weights may end up blending in
+    /// data from a start offset other than the one this method reports.
+    /// [reverse] documents a separate restriction, on [Weight::concat], for
+    /// weights used in a reversed pass.
+    pub fn search<B: Borrow<T>, I: IntoIterator<Item = B>>(
+        &self,
+        input: I,
+    ) -> Option<(usize, usize, W)> {
+        self.search_with(input, |_| false)
+    }
+
+    /// Shared implementation of [Pattern::search] and
+    /// [Pattern::search_prefiltered]: `skip` is consulted, only while no
+    /// thread is alive, to decide whether an input element can't possibly
+    /// begin a match. [Pattern::search] never skips, so every element goes
+    /// through the full per-position loop below; [Pattern::search_prefiltered]
+    /// passes its start-byte prefilter, which the dedicated loop just above
+    /// that one fast-forwards through, bypassing the [Frontier] and
+    /// [Pattern::add_span] bookkeeping entirely for every element it rules
+    /// out, rather than paying for that bookkeeping only to spawn nothing.
+    fn search_with<B: Borrow<T>, I: IntoIterator<Item = B>>(
+        &self,
+        input: I,
+        mut skip: impl FnMut(&T) -> bool,
+    ) -> Option<(usize, usize, W)> {
+        let mut threads: Vec<(PC, usize, W)> = Vec::new();
+        let mut result: Option<(usize, usize, W)> = None;
+        let mut offset = 0;
+        let mut input = input.into_iter();
+        'positions: loop {
+            // Fast-forward over elements that can't possibly begin a match
+            // while no thread is alive, touching nothing but `offset` and
+            // the `skip` check itself.
+            let input = loop {
+                let Some(input) = input.next() else {
+                    break 'positions;
+                };
+                if !(threads.is_empty() && skip(input.borrow())) {
+                    break input;
+                }
+                offset += 1;
+            };
+            let input = input.borrow();
+            threads.push((Self::as_pc(0), offset, W::success()));
+
+            let mut next = Frontier::default();
+            let mut matched = None;
+            for (pc, start, weight) in threads.drain(..) {
+                let accepted = self.add_span(pc.into(), start, offset, &weight, input, &mut next);
+                matched = matched.or(accepted.map(|weight| (start, offset, weight)));
+            }
+            threads = next.threads;
+            offset += 1;
+
+            if let Some((start, end, weight)) = matched {
+                if result.as_ref().is_none_or(|(prev, ..)| start <= *prev) {
+                    result = Some((start, end, weight));
+                }
+            }
+        }
+        // A thread whose closure is accepting doesn't need another input
+        // element to prove it: it's already a complete match ending here.
+        let mut matched = None;
+        for (pc, start, weight) in &threads {
+            let mut accepted = None;
+            for saves in &self.closures[(*pc).into()].accepting {
+                let mut new = weight.clone();
+                for &slot in saves {
+                    new.save(slot, offset);
+                }
+                accepted = merge(accepted, Some(new));
+            }
+            matched = matched.or(accepted.map(|weight| (*start, offset, weight)));
+        }
+        if let Some((start, end, weight)) = matched {
+            if result.as_ref().is_none_or(|(prev, ..)| start <= *prev) {
+                result = Some((start, end, weight));
+            }
+        }
+        result
+    }
+
+    /// Like [Pattern::add], but threads are tagged with the offset at which
+    /// they started, and the live threads and dedup index are passed in
+    /// explicitly instead of living in `self`, since [Pattern::search] keeps
+    /// a set of threads shaped differently than [Pattern::eval]'s.
+    fn add_span(
+        &self,
+        pc: usize,
+        start: usize,
+        offset: usize,
+        weight: &W,
+        input: &T,
+        next: &mut Frontier<PC, W>,
+    ) -> Option<W> {
+        let mut matched = None;
+        for saves in &self.closures[pc].accepting {
+            let mut new = weight.clone();
+            for &slot in saves {
+                new.save(slot, offset);
+            }
+            matched = merge(matched, Some(new));
+        }
+        for (step, saves, succ) in &self.closures[pc].steps {
+            let Some(Inst::Step(s)) = self.pattern.get(*step) else {
+                unreachable!("closures only record the program counters of Step instructions")
+            };
+            let mut weight = weight.clone();
+            for &slot in saves {
+                weight.save(slot, offset);
+            }
+            if let Some(new) = s.step(offset, input).and_then(|cur| weight.concat(&cur)) {
+                next.push(Self::as_pc(*succ), start, new);
+            }
+        }
+        matched
     }
 
     fn as_pc(pc: usize) -> PC {
@@ -160,6 +636,128 @@ where
     }
 }
 
+impl RangeInclusiveSet<u8> {
+    /// Merge overlapping or adjacent ranges so [RangeInclusiveSet::len]
+    /// counts each covered byte value exactly once.
+    fn normalize(&mut self) {
+        self.0.sort_by_key(|range| *range.start());
+        let mut merged: Vec<std::ops::RangeInclusive<u8>> = Vec::new();
+        for range in self.0.drain(..) {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.0 = merged;
+    }
+
+    /// The number of distinct byte values this set covers.
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .map(|range| usize::from(*range.end()) - usize::from(*range.start()) + 1)
+            .sum()
+    }
+
+    /// How common the single most common byte this set covers is, from 0
+    /// (rarest) to 255 (most common), i.e. 255 minus the lowest
+    /// [BYTE_FREQUENCY_RANK] among its members. The
+    /// prefilter built from this set treats every covered byte as a hit, so
+    /// its most common member is what actually caps how rarely the set as a
+    /// whole shows up in typical text; an empty set (no covered bytes)
+    /// counts as maximally unselective rather than trivially rare, so an
+    /// empty [Pattern::start_set] doesn't get mistaken for a great filter.
+    fn selectivity(&self) -> u32 {
+        self.0
+            .iter()
+            .flat_map(|range| *range.start()..=*range.end())
+            .map(|byte| 255 - u32::from(BYTE_FREQUENCY_RANK[usize::from(byte)]))
+            .max()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// The rank of each byte value, from 0 (most common) to 255 (rarest), in
+/// roughly the frequency order aho-corasick's prefilter heuristics use to
+/// pick literals that are cheap to skip past. Used by [Pattern::start_set]
+/// to judge whether a start-byte set is selective enough to be worth
+/// building a prefilter for.
+#[rustfmt::skip]
+static BYTE_FREQUENCY_RANK: [u8; 256] = [
+    97, 98, 99, 100, 101, 102, 103, 104, 105, 76, 75, 106, 107, 108, 109, 110,
+    111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126,
+    0, 71, 66, 77, 78, 79, 80, 65, 69, 70, 81, 82, 64, 67, 63, 83,
+    53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 73, 74, 84, 85, 86, 72,
+    87, 29, 46, 39, 36, 27, 41, 47, 34, 31, 49, 48, 37, 40, 32, 30,
+    44, 50, 35, 33, 28, 38, 45, 42, 51, 43, 52, 88, 89, 90, 91, 68,
+    92, 3, 20, 13, 10, 1, 15, 21, 8, 5, 23, 22, 11, 14, 6, 4,
+    18, 24, 9, 7, 2, 12, 19, 16, 25, 17, 26, 93, 94, 95, 96, 127,
+    128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143,
+    144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159,
+    160, 161, 162, 163, 164, 165, 166, 167, 168, 169, 170, 171, 172, 173, 174, 175,
+    176, 177, 178, 179, 180, 181, 182, 183, 184, 185, 186, 187, 188, 189, 190, 191,
+    192, 193, 194, 195, 196, 197, 198, 199, 200, 201, 202, 203, 204, 205, 206, 207,
+    208, 209, 210, 211, 212, 213, 214, 215, 216, 217, 218, 219, 220, 221, 222, 223,
+    224, 225, 226, 227, 228, 229, 230, 231, 232, 233, 234, 235, 236, 237, 238, 239,
+    240, 241, 242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255,
+];
+
+impl<'a, S, PC, W> Pattern<'a, S, PC, u8, W>
+where
+    S: Step<u8, W>,
+    W: Weight + Clone,
+    PC: Into<usize> + Copy + Eq + std::hash::Hash,
+    usize: TryInto<PC>,
+{
+    /// Build a start-byte prefilter by unioning [Step::accepts_set] across
+    /// every step reachable from program counter 0 without consuming
+    /// input. Returns `None` if some reachable step can't report its
+    /// accepted set, if the resulting set's [RangeInclusiveSet::selectivity]
+    /// says it's too broad for testing membership in it to be worth the
+    /// [Pattern::add_span] work it would save, or if the pattern can already
+    /// match the empty string at the very start: skipping bytes the
+    /// prefilter rules out would also skip past that zero-width match.
+    fn start_set(&self) -> Option<RangeInclusiveSet<u8>> {
+        if !self.closures[0].accepting.is_empty() {
+            return None;
+        }
+        let mut set = RangeInclusiveSet(Vec::new());
+        for (step, ..) in &self.closures[0].steps {
+            let Some(Inst::Step(s)) = self.pattern.get(*step) else {
+                unreachable!("closures only record the program counters of Step instructions")
+            };
+            set.0.extend(s.accepts_set()?.0);
+        }
+        set.normalize();
+        (set.len() <= 128 && set.selectivity() < 192).then_some(set)
+    }
+
+    /// Like [Pattern::search], but first builds a start-byte prefilter from
+    /// [Pattern::start_set]. While no thread is alive, input bytes that the
+    /// prefilter proves can't begin a match are fast-forwarded over by a
+    /// dedicated loop in [Pattern::search_with] that never touches the
+    /// thread bookkeeping [Pattern::search] always pays for, rather than
+    /// spawning one at every position only to immediately drop it. This is a
+    /// plain membership test against the whole start set, not a scan for
+    /// one particular byte: a set with more than one member can't be
+    /// reduced to a single-byte `memchr`-style scan without missing matches
+    /// that start with one of its other members. Subject to the same
+    /// merge-across-start-offsets caveat documented on [Pattern::search].
+    pub fn search_prefiltered<B: Borrow<u8>, I: IntoIterator<Item = B>>(
+        &self,
+        input: I,
+    ) -> Option<(usize, usize, W)> {
+        let prefilter = self.start_set();
+        self.search_with(input, |input| {
+            prefilter.as_ref().is_some_and(|set| !set.contains(input))
+        })
+    }
+}
+
 fn merge<W: Weight>(a: Option<W>, b: Option<W>) -> Option<W> {
     match (a, b) {
         (None, None) => None,
@@ -187,4 +785,139 @@ mod tests {
         assert_eq!(pattern.eval(b"0"), None);
         assert_eq!(pattern.eval(b"AbAz0"), Some(()));
     }
+
+    #[test]
+    fn recognize_match_ending_at_last_element() {
+        let mut pattern: Pattern<_, u8, u8, ()> =
+            Pattern::new(&[Inst::Step(b'A'..=b'A'), Inst::Step(b'B'..=b'B')]);
+
+        pattern.start();
+        pattern.feed(b"AB".iter());
+        assert_eq!(pattern.finish(), Some(()));
+    }
+
+    #[test]
+    fn search_leftmost() {
+        let pattern = Pattern::new(&[
+            Inst::Step(b'A'..=b'A'),
+            Inst::Step(b'a'..=b'z'),
+            Inst::PreferTarget(0u8),
+        ]);
+
+        assert_eq!(pattern.search(b"0"), None);
+        assert_eq!(pattern.search(b"xxAbAz0"), Some((2, 6, ())));
+    }
+
+    #[test]
+    fn search_match_ending_at_last_element() {
+        let insts: [Inst<_, u8>; 2] = [Inst::Step(b'A'..=b'A'), Inst::Step(b'B'..=b'B')];
+        let pattern: Pattern<_, u8, u8, ()> = Pattern::new(&insts);
+
+        assert_eq!(pattern.search(b"xxAB"), Some((2, 4, ())));
+    }
+
+    #[test]
+    fn search_prefiltered_agrees_with_search() {
+        let pattern = Pattern::new(&[
+            Inst::Step(b'A'..=b'A'),
+            Inst::Step(b'a'..=b'z'),
+            Inst::PreferTarget(0u8),
+        ]);
+
+        assert_eq!(pattern.search_prefiltered(b"0"), None);
+        assert_eq!(pattern.search_prefiltered(b"xxAbAz0"), Some((2, 6, ())));
+    }
+
+    #[test]
+    fn search_prefiltered_finds_zero_width_match_at_start() {
+        // `Z?`: the pattern can accept immediately at program counter 0, so
+        // a prefilter built from `Step(Z)` alone would wrongly skip input
+        // that never contains a `Z`.
+        let pattern = Pattern::new(&[Inst::PreferNext(2u8), Inst::Step(b'Z'..=b'Z')]);
+
+        assert_eq!(pattern.search(b"xxx"), Some((0, 0, ())));
+        assert_eq!(pattern.search_prefiltered(b"xxx"), Some((0, 0, ())));
+    }
+
+    #[test]
+    fn captures() {
+        let mut pattern: Pattern<_, u8, u8, Captures> = Pattern::new(&[
+            Inst::Step(b'A'..=b'A'),
+            Inst::Save(0),
+            Inst::Step(b'a'..=b'z'),
+            Inst::Save(1),
+            Inst::PreferTarget(1u8),
+        ]);
+
+        assert_eq!(pattern.eval(b"0"), None);
+
+        let captures = pattern.eval(b"Abcz0").unwrap();
+        assert_eq!(captures.get(0), Some(3));
+        assert_eq!(captures.get(1), Some(4));
+        assert_eq!(captures.get(2), None);
+    }
+
+    #[test]
+    fn reverse_recovers_start() {
+        let insts: [Inst<_, u8>; 2] = [Inst::Step(b'A'..=b'A'), Inst::Step(b'B'..=b'B')];
+        let pattern: Pattern<_, u8, u8, ()> = Pattern::new(&insts);
+
+        let input = b"xxABxx";
+        let (start, end, ()) = pattern.search(input).unwrap();
+        assert_eq!((start, end), (2, 4));
+
+        let reversed = reverse(&insts);
+        let rpattern = Pattern::new(&reversed);
+        let prefix: Vec<u8> = input[..end].iter().rev().copied().collect();
+        let (_, matched_len, ()) = rpattern.search(&prefix).unwrap();
+        assert_eq!(end - matched_len, start);
+    }
+
+    #[test]
+    #[should_panic(expected = "backward jump")]
+    fn reverse_rejects_backward_jump() {
+        let insts: [Inst<_, u8>; 2] = [Inst::Step(b'a'..=b'z'), Inst::PreferTarget(0u8)];
+        reverse(&insts);
+    }
+
+    #[test]
+    #[should_panic(expected = "control flow rejoins")]
+    fn reverse_rejects_non_final_jump_to_end() {
+        // `A(B)?`: `PreferNext(3)` at a non-final pc offers a low-priority
+        // epsilon jump straight to `len`, so "A" alone and "AB" are both
+        // accepting paths through this pattern, and the fall-through from
+        // `Step(B)` also reaches `len`: two predecessors converge there.
+        let insts: [Inst<_, u8>; 3] = [
+            Inst::Step(b'A'..=b'A'),
+            Inst::PreferNext(3u8),
+            Inst::Step(b'B'..=b'B'),
+        ];
+        reverse(&insts);
+    }
+
+    #[test]
+    #[should_panic(expected = "control flow rejoins")]
+    fn reverse_rejects_alternation() {
+        // `(A|B)C`: the high-priority branch `Jump`s from after `Step(A)`
+        // to `Step(C)`, and the low-priority branch falls through from
+        // `Step(B)` straight there too, so `Step(C)` has two distinct
+        // predecessors.
+        let insts: [Inst<_, u8>; 5] = [
+            Inst::PreferNext(3u8),
+            Inst::Step(b'A'..=b'A'),
+            Inst::Jump(4u8),
+            Inst::Step(b'B'..=b'B'),
+            Inst::Step(b'C'..=b'C'),
+        ];
+        reverse(&insts);
+    }
+
+    #[test]
+    fn reverse_allows_jump_to_end() {
+        // `PreferNext(2)` jumps straight to `pattern.len()`, an "accept
+        // immediately" instruction rather than a backward jump; this used to
+        // overflow while mirroring the target.
+        let insts: [Inst<_, u8>; 2] = [Inst::Step(b'A'..=b'A'), Inst::PreferNext(2u8)];
+        reverse(&insts);
+    }
 }